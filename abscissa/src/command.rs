@@ -0,0 +1,28 @@
+//! Subcommands: the `Callable` and `Command` traits
+//!
+//! A [`Command`] is simply an [`Options`] structure which knows how to run
+//! itself once parsed. Applications mount commands as subcommands of their
+//! top-level `Options` structure. The built-in
+//! [`options::CompletionsCommand`][crate::options::CompletionsCommand]
+//! implements only [`Callable`], not [`Options`], so it is not a `Command`.
+
+use options::Options;
+
+/// A type which can be invoked, performing some action.
+///
+/// This is kept separate from [`Options`] so that option-parsing and
+/// command-running remain independently testable.
+pub trait Callable {
+    /// Call this command, performing whatever action it represents.
+    fn call(&self);
+}
+
+/// A subcommand: the combination of declarative option parsing and an
+/// action to run once parsing succeeds.
+///
+/// Blanket-implemented for any type which implements both [`Options`] and
+/// [`Callable`]; application authors should not need to implement this
+/// directly.
+pub trait Command: Options + Callable {}
+
+impl<T: Options + Callable> Command for T {}
@@ -0,0 +1,70 @@
+//! Declarative global application configuration.
+//!
+//! An application's configuration is a single struct implementing
+//! [`GlobalConfig`], parsed from TOML and stored behind a `RwLock` on a
+//! `lazy_static` (wired up for a particular type by the
+//! `impl_global_config!` macro). [`ConfigReader`] is a thin smart pointer
+//! over the read half of that lock, so call sites can treat a global
+//! config access like an ordinary borrow.
+//!
+//! By default configuration is loaded once at startup and never changes.
+//! Applications that want edits to the backing TOML file to take effect
+//! without a restart can opt into the [`watch`] submodule, which turns this
+//! one-shot load into a reactive subsystem: it monitors the file for
+//! writes, re-parses and validates it, and atomically swaps in the new
+//! value via [`GlobalConfig::set_global`].
+
+use std::fs;
+use std::ops::Deref;
+use std::path::Path;
+use std::sync::RwLockReadGuard;
+
+use failure::ResultExt;
+use serde::de::DeserializeOwned;
+
+use error::{FrameworkError, FrameworkErrorKind};
+
+pub mod watch;
+
+pub use self::watch::{ConfigWatcher, ReloadCallback, ValidateHook};
+
+/// Trait implemented by an application's top-level configuration struct.
+///
+/// Implementors store their parsed value behind a `RwLock` on a
+/// `lazy_static` so the current configuration can be read from anywhere in
+/// the application and, with [`ConfigWatcher`], updated while the
+/// application is running.
+pub trait GlobalConfig: Sized + Send + Sync + 'static {
+    /// Obtain a read lock on the global configuration.
+    fn get_global() -> ConfigReader<Self>;
+
+    /// Replace the global configuration.
+    fn set_global(config: Self);
+}
+
+/// A read guard over a [`GlobalConfig`] value, returned by
+/// [`GlobalConfig::get_global`].
+pub struct ConfigReader<C: 'static>(RwLockReadGuard<'static, C>);
+
+impl<C: 'static> ConfigReader<C> {
+    /// Wrap a lock guard obtained from a `GlobalConfig`'s backing
+    /// `RwLock`.
+    pub fn new(guard: RwLockReadGuard<'static, C>) -> Self {
+        ConfigReader(guard)
+    }
+}
+
+impl<C: 'static> Deref for ConfigReader<C> {
+    type Target = C;
+
+    fn deref(&self) -> &C {
+        &self.0
+    }
+}
+
+/// Parse a `GlobalConfig` value from the TOML file at `path`.
+pub fn load_toml<C: DeserializeOwned>(path: &Path) -> Result<C, FrameworkError> {
+    let toml_string = fs::read_to_string(path).context(FrameworkErrorKind::IoError)?;
+    let config = toml::from_str(&toml_string).context(FrameworkErrorKind::ConfigError)?;
+    Ok(config)
+}
@@ -0,0 +1,236 @@
+//! Filesystem-backed hot-reloading for [`GlobalConfig`] values.
+//!
+//! [`ConfigWatcher::watch`] monitors the TOML file a [`GlobalConfig`] was
+//! originally loaded from. On every write, it re-parses the file, runs the
+//! caller's [`ValidateHook`] against the result, and only then swaps it
+//! into the global slot via [`GlobalConfig::set_global`] — so a malformed
+//! edit is rejected and the previous configuration keeps serving. After a
+//! successful swap, every registered [`ReloadCallback`] runs, so components
+//! such as `LoggingConfig` can reconfigure themselves live.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use failure::ResultExt;
+use notify::{watcher, RecursiveMode, Watcher};
+use serde::de::DeserializeOwned;
+
+use config::{load_toml, GlobalConfig};
+use error::{FrameworkError, FrameworkErrorKind};
+
+/// A callback invoked after the global configuration has been successfully
+/// reloaded.
+pub type ReloadCallback = Box<dyn Fn() + Send + 'static>;
+
+/// A validation hook run against a freshly-parsed configuration before it
+/// replaces the current global configuration.
+///
+/// Returning `Err` rejects the reload: the edit which triggered it is
+/// logged and the previous (valid) configuration is retained.
+pub type ValidateHook<C> = Box<dyn Fn(&C) -> Result<(), FrameworkError> + Send + 'static>;
+
+/// Watches a [`GlobalConfig`]'s backing TOML file for changes, reloading
+/// and swapping it in on every valid edit.
+///
+/// Dropping the `ConfigWatcher` stops the background watch thread.
+pub struct ConfigWatcher {
+    path: PathBuf,
+    watcher: Box<dyn Watcher + Send>,
+    handle: Option<JoinHandle<()>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl ConfigWatcher {
+    /// Begin watching `path` for changes. Each time it is written, the file
+    /// is re-parsed as `C`, passed to `validate`, and — if validation
+    /// succeeds — installed as the new global configuration, after which
+    /// `on_reload` is invoked.
+    pub fn watch<C>(
+        path: impl AsRef<Path>,
+        validate: ValidateHook<C>,
+        on_reload: ReloadCallback,
+    ) -> Result<Self, FrameworkError>
+    where
+        C: GlobalConfig + DeserializeOwned,
+    {
+        let path = path.as_ref().to_path_buf();
+        let (tx, rx) = channel();
+
+        let mut fs_watcher =
+            watcher(tx, Duration::from_secs(1)).context(FrameworkErrorKind::IoError)?;
+
+        fs_watcher
+            .watch(&path, RecursiveMode::NonRecursive)
+            .context(FrameworkErrorKind::IoError)?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        let thread_path = path.clone();
+
+        let handle = thread::spawn(move || {
+            while !thread_stop.load(Ordering::Relaxed) {
+                match rx.recv_timeout(Duration::from_millis(500)) {
+                    Ok(_event) => Self::reload::<C>(&thread_path, &validate, &on_reload),
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+        });
+
+        Ok(ConfigWatcher {
+            path,
+            watcher: Box::new(fs_watcher),
+            handle: Some(handle),
+            stop,
+        })
+    }
+
+    /// Re-parse, validate, and (if valid) install the watched file as the
+    /// new global configuration.
+    fn reload<C>(path: &Path, validate: &ValidateHook<C>, on_reload: &ReloadCallback)
+    where
+        C: GlobalConfig + DeserializeOwned,
+    {
+        let new_config = match load_toml::<C>(path) {
+            Ok(config) => config,
+            Err(e) => {
+                status_err!("failed to reload {}: {}", path.display(), e);
+                return;
+            }
+        };
+
+        if let Err(e) = validate(&new_config) {
+            status_err!("rejected reload of {}: {}", path.display(), e);
+            return;
+        }
+
+        C::set_global(new_config);
+        on_reload();
+    }
+
+    /// Path to the file being watched.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for ConfigWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::Mutex;
+    use std::sync::RwLock as StdRwLock;
+
+    use serde::Deserialize;
+
+    use config::ConfigReader;
+
+    #[derive(Clone, Debug, Deserialize, PartialEq)]
+    struct TestConfig {
+        value: u32,
+    }
+
+    lazy_static! {
+        static ref TEST_CONFIG: StdRwLock<TestConfig> = StdRwLock::new(TestConfig { value: 0 });
+        // `TestConfig` is a single process-wide global, like any other
+        // `GlobalConfig`; serialize the tests below so they don't stomp on
+        // each other's state.
+        static ref TEST_LOCK: Mutex<()> = Mutex::new(());
+    }
+
+    impl GlobalConfig for TestConfig {
+        fn get_global() -> ConfigReader<Self> {
+            ConfigReader::new(TEST_CONFIG.read().unwrap())
+        }
+
+        fn set_global(config: Self) {
+            *TEST_CONFIG.write().unwrap() = config;
+        }
+    }
+
+    /// Write `contents` to a fresh temporary file and return its path.
+    fn write_temp_file(name: &str, contents: &str) -> PathBuf {
+        let mut path = std::env::temp_dir();
+        path.push(format!("abscissa-config-watch-test-{}-{}", std::process::id(), name));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn counting_callback() -> (ReloadCallback, Arc<AtomicUsize>) {
+        let count = Arc::new(AtomicUsize::new(0));
+        let counted = count.clone();
+        let callback: ReloadCallback = Box::new(move || {
+            counted.fetch_add(1, Ordering::SeqCst);
+        });
+        (callback, count)
+    }
+
+    fn accepting_validator() -> ValidateHook<TestConfig> {
+        Box::new(|_config: &TestConfig| Ok(()))
+    }
+
+    #[test]
+    fn reload_installs_a_valid_config_and_fires_the_callback_once() {
+        let _guard = TEST_LOCK.lock().unwrap();
+        TestConfig::set_global(TestConfig { value: 0 });
+
+        let path = write_temp_file("valid", "value = 42\n");
+        let (callback, count) = counting_callback();
+
+        ConfigWatcher::reload::<TestConfig>(&path, &accepting_validator(), &callback);
+
+        assert_eq!(TestConfig::get_global().value, 42);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn reload_rejects_malformed_toml_and_retains_the_previous_config() {
+        let _guard = TEST_LOCK.lock().unwrap();
+        TestConfig::set_global(TestConfig { value: 7 });
+
+        let path = write_temp_file("malformed", "this is not valid toml [[[");
+        let (callback, count) = counting_callback();
+
+        ConfigWatcher::reload::<TestConfig>(&path, &accepting_validator(), &callback);
+
+        assert_eq!(TestConfig::get_global().value, 7);
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn reload_rejects_a_config_that_fails_validation() {
+        let _guard = TEST_LOCK.lock().unwrap();
+        TestConfig::set_global(TestConfig { value: 7 });
+
+        let path = write_temp_file("invalid-value", "value = 99\n");
+        let (callback, count) = counting_callback();
+        let rejecting_validator: ValidateHook<TestConfig> =
+            Box::new(|_config: &TestConfig| Err(FrameworkErrorKind::ConfigError.into()));
+
+        ConfigWatcher::reload::<TestConfig>(&path, &rejecting_validator, &callback);
+
+        assert_eq!(TestConfig::get_global().value, 7);
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+
+        fs::remove_file(&path).ok();
+    }
+}
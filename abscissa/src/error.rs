@@ -0,0 +1,79 @@
+//! Abscissa's unified error-handling subsystem.
+//!
+//! Every part of the framework which can fail returns a [`FrameworkError`],
+//! a `failure`-based error type carrying a [`FrameworkErrorKind`] alongside
+//! a backtrace and (where available) the underlying cause. Application
+//! code is free to use `failure::Error` (re-exported here as [`Error`])
+//! directly for its own errors.
+
+use std::fmt::{self, Display};
+
+use failure::{Backtrace, Context, Fail};
+
+/// Re-export of `failure::Error`, for application code that wants a single
+/// catch-all error type without defining its own `Fail`-derived kind.
+pub use failure::Error;
+
+/// Re-export of the `failure::Fail` trait, which every Abscissa error type
+/// (including [`FrameworkError`]) implements.
+pub use failure::Fail;
+
+/// Kinds of errors the framework itself can produce.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Fail)]
+pub enum FrameworkErrorKind {
+    /// Error parsing or validating configuration.
+    #[fail(display = "config error")]
+    ConfigError,
+
+    /// Error parsing command-line options.
+    #[fail(display = "parse error")]
+    ParseError,
+
+    /// An I/O error (reading a config or log file, etc.)
+    #[fail(display = "I/O error")]
+    IoError,
+}
+
+/// The framework's unified error type.
+///
+/// Wraps a [`FrameworkErrorKind`] together with a `failure::Context`, so
+/// the original cause (if any) and a backtrace are preserved.
+#[derive(Debug)]
+pub struct FrameworkError {
+    inner: Context<FrameworkErrorKind>,
+}
+
+impl FrameworkError {
+    /// Obtain the kind of error this is.
+    pub fn kind(&self) -> FrameworkErrorKind {
+        *self.inner.get_context()
+    }
+}
+
+impl Fail for FrameworkError {
+    fn cause(&self) -> Option<&dyn Fail> {
+        self.inner.cause()
+    }
+
+    fn backtrace(&self) -> Option<&Backtrace> {
+        self.inner.backtrace()
+    }
+}
+
+impl Display for FrameworkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(&self.inner, f)
+    }
+}
+
+impl From<FrameworkErrorKind> for FrameworkError {
+    fn from(kind: FrameworkErrorKind) -> Self {
+        Context::new(kind).into()
+    }
+}
+
+impl From<Context<FrameworkErrorKind>> for FrameworkError {
+    fn from(inner: Context<FrameworkErrorKind>) -> Self {
+        FrameworkError { inner }
+    }
+}
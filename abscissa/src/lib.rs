@@ -77,14 +77,24 @@ extern crate lazy_static;
 #[cfg(feature = "logging")]
 pub extern crate log;
 #[cfg(feature = "config")]
+extern crate notify;
+#[cfg(feature = "config")]
 extern crate serde;
 #[cfg(feature = "simplelog")]
 extern crate simplelog;
+#[cfg(feature = "secrets")]
+extern crate subtle;
 extern crate term;
+#[cfg(feature = "config")]
+extern crate toml;
+#[cfg(feature = "secrets")]
+extern crate zeroize;
 
 #[cfg(all(test, feature = "options"))]
 #[macro_use]
 extern crate assert_matches;
+#[cfg(all(test, feature = "logging"))]
+extern crate serde_json;
 
 // Load macros first
 #[macro_use]
@@ -110,12 +120,12 @@ pub mod util;
 pub use application::{boot, Application, ApplicationPath, Component, Components};
 #[cfg(feature = "options")]
 pub use command::{Callable, Command};
-pub use config::{ConfigReader, GlobalConfig};
+pub use config::{ConfigReader, ConfigWatcher, GlobalConfig};
 pub use error::{Error, Fail, FrameworkError, FrameworkErrorKind};
 #[cfg(feature = "logging")]
-pub use logging::LoggingConfig;
+pub use logging::{LogFormat, LogWriter, LoggingConfig};
 #[cfg(feature = "options")]
-pub use options::Options;
+pub use options::{CompletionsCommand, Options, Shell};
 #[cfg(feature = "secrets")]
 pub use secrets::Secret;
 pub use shell::{status, ColorConfig, Stream};
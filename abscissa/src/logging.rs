@@ -0,0 +1,292 @@
+//! Application-level logging, built on the `log` crate.
+//!
+//! [`LoggingConfig`] configures where log records go (stdout or a file) and
+//! how they're formatted: human-readable lines via `simplelog`
+//! ([`LogFormat::Plain`], the default), or one JSON object per record
+//! ([`LogFormat::Json`]) for services shipping logs to an aggregator.
+
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use failure::ResultExt;
+use log::{self, LevelFilter, Log, Metadata, Record};
+use simplelog::{Config as SimplelogConfig, SimpleLogger, WriteLogger};
+
+use error::{FrameworkError, FrameworkErrorKind};
+use util::Version;
+
+/// Output format for log records.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum LogFormat {
+    /// Human-readable lines (the default), rendered by `simplelog`.
+    Plain,
+    /// One JSON object per record: `timestamp`, `level`, `target`,
+    /// `module_path`, `message`, and any static context fields.
+    Json,
+}
+
+impl Default for LogFormat {
+    fn default() -> Self {
+        LogFormat::Plain
+    }
+}
+
+/// Where log output is written.
+#[derive(Clone, Debug)]
+pub enum LogWriter {
+    /// Standard output.
+    Stdout,
+    /// A file at the given path, opened in append mode.
+    File(PathBuf),
+}
+
+/// Logging configuration for an application.
+#[derive(Clone, Debug)]
+pub struct LoggingConfig {
+    /// Minimum level of records to emit.
+    pub level: LevelFilter,
+    /// Where to write records.
+    pub writer: LogWriter,
+    /// How to format records.
+    pub format: LogFormat,
+    /// Static key/value fields attached to every record in `Json` mode
+    /// (e.g. an application's name and version). Ignored in `Plain` mode.
+    pub context: Vec<(String, String)>,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        LoggingConfig {
+            level: LevelFilter::Info,
+            writer: LogWriter::Stdout,
+            format: LogFormat::default(),
+            context: Vec::new(),
+        }
+    }
+}
+
+impl LoggingConfig {
+    /// Attach a static key/value field to every record emitted in `Json`
+    /// mode.
+    pub fn with_context(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.context.push((key.into(), value.into()));
+        self
+    }
+
+    /// Attach an application's name and version as static JSON context,
+    /// under the `app` and `version` keys.
+    pub fn with_version(self, version: &Version) -> Self {
+        self.with_context("app", version.name().to_owned())
+            .with_context("version", version.version().to_owned())
+    }
+
+    /// Initialize the `log` crate's global logger from this
+    /// configuration.
+    pub fn init(self) -> Result<(), FrameworkError> {
+        match self.format {
+            LogFormat::Plain => self.init_plain(),
+            LogFormat::Json => self.init_json(),
+        }
+    }
+
+    fn init_plain(self) -> Result<(), FrameworkError> {
+        let simplelog_config = SimplelogConfig::default();
+
+        match self.writer {
+            LogWriter::Stdout => {
+                SimpleLogger::init(self.level, simplelog_config).context(FrameworkErrorKind::IoError)?
+            }
+            LogWriter::File(path) => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&path)
+                    .context(FrameworkErrorKind::IoError)?;
+
+                WriteLogger::init(self.level, simplelog_config, file)
+                    .context(FrameworkErrorKind::IoError)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn init_json(self) -> Result<(), FrameworkError> {
+        let writer: Box<dyn Write + Send> = match self.writer {
+            LogWriter::Stdout => Box::new(io::stdout()),
+            LogWriter::File(path) => Box::new(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&path)
+                    .context(FrameworkErrorKind::IoError)?,
+            ),
+        };
+
+        let logger = JsonLogger {
+            level: self.level,
+            context: self.context,
+            writer: Mutex::new(writer),
+        };
+
+        log::set_boxed_logger(Box::new(logger)).context(FrameworkErrorKind::IoError)?;
+        log::set_max_level(self.level);
+
+        Ok(())
+    }
+}
+
+/// A [`Log`] implementation which writes one JSON object per record.
+struct JsonLogger {
+    level: LevelFilter,
+    context: Vec<(String, String)>,
+    writer: Mutex<Box<dyn Write + Send>>,
+}
+
+impl Log for JsonLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+
+        let mut line = format!(
+            "{{\"timestamp\":{}.{:06},\"level\":\"{}\",\"target\":{}",
+            timestamp.as_secs(),
+            timestamp.subsec_micros(),
+            record.level(),
+            json_string(record.target()),
+        );
+
+        if let Some(module_path) = record.module_path() {
+            line.push_str(&format!(",\"module_path\":{}", json_string(module_path)));
+        }
+
+        line.push_str(&format!(
+            ",\"message\":{}",
+            json_string(&record.args().to_string())
+        ));
+
+        for (key, value) in &self.context {
+            line.push_str(&format!(",{}:{}", json_string(key), json_string(value)));
+        }
+
+        line.push_str("}\n");
+
+        if let Ok(mut writer) = self.writer.lock() {
+            let _ = writer.write_all(line.as_bytes());
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut writer) = self.writer.lock() {
+            let _ = writer.flush();
+        }
+    }
+}
+
+/// JSON-quote and escape a string.
+fn json_string(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+    use std::sync::Arc;
+
+    use log::{Level, Record};
+
+    /// An in-memory `Write` whose contents can be inspected after logging,
+    /// shared between the `JsonLogger` under test and the assertions below.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn json_lines_parse_and_carry_the_expected_fields() {
+        let buffer = SharedBuffer::default();
+        let logger = JsonLogger {
+            level: LevelFilter::Info,
+            context: vec![("app".to_string(), "my-app".to_string())],
+            writer: Mutex::new(Box::new(buffer.clone())),
+        };
+
+        let record = Record::builder()
+            .level(Level::Info)
+            .target("my::target")
+            .module_path(Some("my::module"))
+            .args(format_args!("hello \"world\""))
+            .build();
+
+        logger.log(&record);
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert!(output.ends_with('\n'));
+
+        let value: serde_json::Value = serde_json::from_str(output.trim_end()).unwrap();
+
+        assert_eq!(value["level"], "INFO");
+        assert_eq!(value["target"], "my::target");
+        assert_eq!(value["module_path"], "my::module");
+        assert_eq!(value["message"], "hello \"world\"");
+        assert_eq!(value["app"], "my-app");
+        assert!(value["timestamp"].is_number());
+    }
+
+    #[test]
+    fn disabled_levels_are_not_written() {
+        let buffer = SharedBuffer::default();
+        let logger = JsonLogger {
+            level: LevelFilter::Warn,
+            context: Vec::new(),
+            writer: Mutex::new(Box::new(buffer.clone())),
+        };
+
+        let record = Record::builder()
+            .level(Level::Info)
+            .target("my::target")
+            .args(format_args!("should not appear"))
+            .build();
+
+        logger.log(&record);
+
+        assert!(buffer.0.lock().unwrap().is_empty());
+    }
+}
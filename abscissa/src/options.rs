@@ -0,0 +1,206 @@
+//! Declarative command-line option parsing, forked from [gumdrop].
+//!
+//! Abscissa's option parser works the same way as [gumdrop]: derive
+//! [`Options`] on a struct or enum, and each field becomes a long/short
+//! flag (or, for fields marked `free`, a positional argument). The fork
+//! adds a number of improvements on top of upstream gumdrop, including
+//! tighter integration with the rest of the framework (e.g. overriding
+//! configuration settings from parsed options) and, as of this module,
+//! [shell completion generation](completions).
+//!
+//! [gumdrop]: https://github.com/murarth/gumdrop
+
+use std::error;
+use std::fmt;
+
+pub mod completions;
+
+pub use self::completions::{CompletionsCommand, Shell};
+
+/// Controls how a parser deals with `--` and interleaved free arguments.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ParsingStyle {
+    /// Parse options and free arguments in any order (the default).
+    AllOptions,
+    /// Stop parsing options as soon as the first free argument is seen;
+    /// everything after it (including things that look like flags) is
+    /// treated as a free argument.
+    StopAtFirstFree,
+}
+
+impl Default for ParsingStyle {
+    fn default() -> Self {
+        ParsingStyle::AllOptions
+    }
+}
+
+/// An error encountered while parsing command-line arguments.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Error(String);
+
+impl Error {
+    /// Create a new parsing error with the given message.
+    pub fn new<T: Into<String>>(message: T) -> Self {
+        Error(message.into())
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl error::Error for Error {
+    fn description(&self) -> &str {
+        &self.0
+    }
+}
+
+/// How many arguments a single option accepts.
+///
+/// Exposed (alongside [`OptSpec`] and [`FreeSpec`]) so that tooling such as
+/// the [completions](completions) generator can walk an `Options`
+/// structure's flags without re-parsing its human-oriented
+/// [`usage`](Options::usage) string.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Arity {
+    /// A boolean flag which takes no argument, e.g. `--verbose`.
+    Flag,
+    /// An option which takes exactly one argument, e.g. `--output FILE`.
+    Single,
+    /// An option which may be repeated, collecting one argument each time.
+    Multiple,
+}
+
+/// Static metadata describing a single declared flag.
+#[derive(Copy, Clone, Debug)]
+pub struct OptSpec {
+    /// Long form, e.g. `Some("output")` for `--output`.
+    pub long: Option<&'static str>,
+    /// Short form, e.g. `Some('o')` for `-o`.
+    pub short: Option<char>,
+    /// One-line help text, as shown in `usage()`.
+    pub help: &'static str,
+    /// How many arguments this flag accepts.
+    pub arity: Arity,
+}
+
+/// Static metadata describing a single positional (free) argument.
+#[derive(Copy, Clone, Debug)]
+pub struct FreeSpec {
+    /// Name of the argument, as shown in `usage()`.
+    pub name: &'static str,
+    /// One-line help text.
+    pub help: &'static str,
+}
+
+/// Trait for options structures which can be constructed by parsing
+/// command-line arguments.
+///
+/// This is normally derived with `#[derive(Options)]` rather than
+/// implemented by hand; see the [module-level documentation](self) for an
+/// overview of how fields map to flags.
+pub trait Options {
+    /// Parse the given arguments, using the given [`ParsingStyle`].
+    fn parse_args<S: AsRef<str>>(args: &[S], style: ParsingStyle) -> Result<Self, Error>
+    where
+        Self: Sized;
+
+    /// Parse the given arguments using the default [`ParsingStyle`].
+    fn parse<S: AsRef<str>>(args: &[S]) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        Self::parse_args(args, ParsingStyle::default())
+    }
+
+    /// Name of the subcommand selected while parsing, if any.
+    fn command_name(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// The selected subcommand's own parsed options, if any.
+    fn command(&self) -> Option<&dyn Options> {
+        None
+    }
+
+    /// Usage string for this options structure alone (not including
+    /// subcommands).
+    fn self_usage() -> &'static str
+    where
+        Self: Sized;
+
+    /// Usage string for this options structure, including subcommands.
+    fn usage() -> &'static str
+    where
+        Self: Sized,
+    {
+        Self::self_usage()
+    }
+
+    /// Listing of subcommand names and descriptions, formatted for
+    /// display.
+    fn command_list() -> Option<&'static str>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    /// Usage string for a particular named subcommand.
+    fn command_usage(_command: &str) -> Option<&'static str>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    /// Declarative metadata for every flag this options structure accepts,
+    /// in declaration order.
+    ///
+    /// The derive macro fills this in automatically; it exists so that
+    /// tooling (e.g. [`completions`]) can enumerate flags without parsing
+    /// [`usage`](Options::usage).
+    fn opt_specs() -> &'static [OptSpec]
+    where
+        Self: Sized,
+    {
+        &[]
+    }
+
+    /// Declarative metadata for this options structure's free (positional)
+    /// arguments, in declaration order.
+    fn free_specs() -> &'static [FreeSpec]
+    where
+        Self: Sized,
+    {
+        &[]
+    }
+
+    /// Names and one-line descriptions of this options structure's
+    /// subcommands, if any.
+    fn subcommand_specs() -> &'static [(&'static str, &'static str)]
+    where
+        Self: Sized,
+    {
+        &[]
+    }
+
+    /// Render a shell completion script for this options structure.
+    ///
+    /// `name` is the name of the binary being completed (normally
+    /// `env!("CARGO_PKG_NAME")` or `Application::name()`).
+    fn completions(name: &str, shell: Shell) -> String
+    where
+        Self: Sized,
+    {
+        completions::generate(
+            name,
+            shell,
+            Self::opt_specs(),
+            Self::free_specs(),
+            Self::subcommand_specs(),
+        )
+    }
+}
@@ -0,0 +1,488 @@
+//! Shell completion script generation.
+//!
+//! Walks the declarative option metadata ([`OptSpec`], [`FreeSpec`], and
+//! subcommand names) exposed by `#[derive(Options)]` and renders a
+//! completion script for bash, zsh, or fish. [`CompletionsCommand`] wraps
+//! a rendered script in a [`Callable`] which prints it. It does not
+//! implement [`Options`], so it is not a
+//! [`Command`][crate::command::Command] and is not parsed from arguments
+//! as a subcommand.
+
+use std::fmt;
+
+use command::Callable;
+use options::{Arity, FreeSpec, OptSpec, Options};
+
+/// Shells that [`CompletionsCommand`] knows how to generate scripts for.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Shell {
+    /// GNU Bash
+    Bash,
+    /// Z shell
+    Zsh,
+    /// fish
+    Fish,
+}
+
+impl Shell {
+    /// Canonical lowercase name for this shell, e.g. `"bash"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+        }
+    }
+}
+
+impl fmt::Display for Shell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Render a completion script for `shell`, given an options structure's
+/// declarative metadata.
+pub fn generate(
+    name: &str,
+    shell: Shell,
+    opts: &[OptSpec],
+    free: &[FreeSpec],
+    subcommands: &[(&'static str, &'static str)],
+) -> String {
+    match shell {
+        Shell::Bash => bash(name, opts, free, subcommands),
+        Shell::Zsh => zsh(name, opts, free, subcommands),
+        Shell::Fish => fish(name, opts, free, subcommands),
+    }
+}
+
+/// Every `--long` and `-s`hort flag string for a set of options, in
+/// declaration order.
+fn flag_strings(opts: &[OptSpec]) -> Vec<String> {
+    let mut flags = Vec::new();
+
+    for opt in opts {
+        if let Some(long) = opt.long {
+            flags.push(format!("--{}", long));
+        }
+
+        if let Some(short) = opt.short {
+            flags.push(format!("-{}", short));
+        }
+    }
+
+    flags
+}
+
+/// `--long`/`-s`hort strings for every flag whose [`Arity`] takes an
+/// argument (`Single` or `Multiple`), so bash can tell those apart from
+/// plain boolean flags and offer file completion for their value instead
+/// of the next flag/subcommand name.
+fn value_flag_strings(opts: &[OptSpec]) -> Vec<String> {
+    let mut flags = Vec::new();
+
+    for opt in opts {
+        if opt.arity == Arity::Flag {
+            continue;
+        }
+
+        if let Some(long) = opt.long {
+            flags.push(format!("--{}", long));
+        }
+
+        if let Some(short) = opt.short {
+            flags.push(format!("-{}", short));
+        }
+    }
+
+    flags
+}
+
+fn bash(
+    name: &str,
+    opts: &[OptSpec],
+    free: &[FreeSpec],
+    subcommands: &[(&'static str, &'static str)],
+) -> String {
+    let flags = flag_strings(opts).join(" ");
+    let value_flags = value_flag_strings(opts).join(" ");
+    let commands = subcommands
+        .iter()
+        .map(|(cmd, _)| *cmd)
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    // With no subcommands to offer, a free (positional) argument is most
+    // often a path, so fall back to bash's own file completion for it
+    // rather than proposing nothing.
+    let positional_completion = if !subcommands.is_empty() {
+        "COMPREPLY=( $(compgen -W \"$commands\" -- \"$cur\") )"
+    } else if !free.is_empty() {
+        "COMPREPLY=( $(compgen -f -- \"$cur\") )"
+    } else {
+        "COMPREPLY=()"
+    };
+
+    format!(
+        "_{name}() {{\n\
+         \x20\x20\x20\x20local cur prev opts value_opts commands\n\
+         \x20\x20\x20\x20cur=\"${{COMP_WORDS[COMP_CWORD]}}\"\n\
+         \x20\x20\x20\x20prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"\n\
+         \x20\x20\x20\x20opts=\"{flags}\"\n\
+         \x20\x20\x20\x20value_opts=\"{value_flags}\"\n\
+         \x20\x20\x20\x20commands=\"{commands}\"\n\n\
+         \x20\x20\x20\x20for value_opt in $value_opts; do\n\
+         \x20\x20\x20\x20\x20\x20\x20\x20if [[ \"$prev\" == \"$value_opt\" ]]; then\n\
+         \x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20COMPREPLY=( $(compgen -f -- \"$cur\") )\n\
+         \x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20return\n\
+         \x20\x20\x20\x20\x20\x20\x20\x20fi\n\
+         \x20\x20\x20\x20done\n\n\
+         \x20\x20\x20\x20if [[ \"$cur\" == -* ]]; then\n\
+         \x20\x20\x20\x20\x20\x20\x20\x20COMPREPLY=( $(compgen -W \"$opts\" -- \"$cur\") )\n\
+         \x20\x20\x20\x20else\n\
+         \x20\x20\x20\x20\x20\x20\x20\x20{positional_completion}\n\
+         \x20\x20\x20\x20fi\n\
+         }}\n\
+         complete -F _{name} {name}\n",
+        name = name,
+        flags = flags,
+        value_flags = value_flags,
+        commands = commands,
+        positional_completion = positional_completion,
+    )
+}
+
+/// Join lines of a zsh statement that spans several `\`-continued lines,
+/// appending the continuation to every line but the last. Joining with a
+/// bare `"\n"` instead would terminate the statement after the first line,
+/// turning every subsequent line into its own (invalid) top-level command.
+fn continued(lines: Vec<String>) -> String {
+    lines.join(" \\\n")
+}
+
+/// Render one `_arguments` entry for a flag. `Single`/`Multiple` flags get
+/// a `:value:_files` value-completion action (so e.g. `--output <TAB>`
+/// offers filenames); `Multiple` flags are additionally marked with a
+/// leading `'*'` so `_arguments` lets them repeat. Plain `Flag`s get
+/// neither, matching a boolean switch that takes no value.
+fn zsh_flag_spec(opt: &OptSpec) -> String {
+    let desc = match (opt.long, opt.short) {
+        (Some(long), Some(short)) => format!("{{-{short},--{long}}}", short = short, long = long),
+        (Some(long), None) => format!("--{}", long),
+        (None, Some(short)) => format!("-{}", short),
+        (None, None) => return String::new(),
+    };
+
+    match opt.arity {
+        Arity::Flag => format!("    {desc}'[{help}]'", desc = desc, help = opt.help),
+        Arity::Single => format!(
+            "    {desc}'[{help}]:value:_files'",
+            desc = desc,
+            help = opt.help
+        ),
+        Arity::Multiple => format!(
+            "    '*'{desc}'[{help}]:value:_files'",
+            desc = desc,
+            help = opt.help
+        ),
+    }
+}
+
+fn zsh(
+    name: &str,
+    opts: &[OptSpec],
+    free: &[FreeSpec],
+    subcommands: &[(&'static str, &'static str)],
+) -> String {
+    let mut arg_specs: Vec<String> = opts.iter().map(zsh_flag_spec).collect();
+
+    // Free (positional) arguments get their own numbered `_arguments`
+    // slot, named after `FreeSpec::name` and completed as files. When
+    // there are subcommands, position 1 is reserved for the subcommand
+    // name (dispatched below via `$state`), so free arguments start at 2;
+    // otherwise they start at 1.
+    let first_free_position = if subcommands.is_empty() {
+        1
+    } else {
+        arg_specs.push("    '1: :->command'".to_string());
+        2
+    };
+
+    for (i, f) in free.iter().enumerate() {
+        arg_specs.push(format!(
+            "    '{}:{}:_files'",
+            first_free_position + i,
+            f.name
+        ));
+    }
+
+    let case_block = if subcommands.is_empty() {
+        String::new()
+    } else {
+        let command_lines = continued(
+            subcommands
+                .iter()
+                .map(|(cmd, help)| format!("    '{}:{}'", cmd, help))
+                .collect(),
+        );
+
+        format!(
+            "\x20\x20case $state in\n\
+             \x20\x20\x20\x20command)\n\
+             \x20\x20\x20\x20\x20\x20_values 'command' \\\n\
+             {command_lines}\n\
+             \x20\x20\x20\x20\x20\x20;;\n\
+             \x20\x20esac\n",
+            command_lines = command_lines,
+        )
+    };
+
+    finish_zsh(name, arg_specs, case_block)
+}
+
+/// Assemble the final zsh completion script from a fully-built list of
+/// `_arguments` entries and an (optional) `case $state` dispatch block.
+fn finish_zsh(name: &str, arg_specs: Vec<String>, case_block: String) -> String {
+    let arguments_block = continued(arg_specs);
+
+    format!(
+        "#compdef {name}\n\n\
+         _{name}() {{\n\
+         \x20\x20_arguments -C \\\n\
+         {arguments_block}\n\n\
+         {case_block}\
+         }}\n\n\
+         _{name}\n",
+        name = name,
+        arguments_block = arguments_block,
+        case_block = case_block,
+    )
+}
+
+fn fish(
+    name: &str,
+    opts: &[OptSpec],
+    free: &[FreeSpec],
+    subcommands: &[(&'static str, &'static str)],
+) -> String {
+    let mut lines = Vec::new();
+
+    for opt in opts {
+        let mut line = format!("complete -c {} ", name);
+
+        if let Some(short) = opt.short {
+            line.push_str(&format!("-s {} ", short));
+        }
+
+        if let Some(long) = opt.long {
+            line.push_str(&format!("-l {} ", long));
+        }
+
+        // `-r` tells fish this flag requires an argument, so it doesn't
+        // offer the next flag/subcommand name as if this one needed none.
+        if opt.arity != Arity::Flag {
+            line.push_str("-r ");
+        }
+
+        line.push_str(&format!("-d '{}'", opt.help));
+        lines.push(line);
+    }
+
+    for (cmd, help) in subcommands {
+        lines.push(format!(
+            "complete -c {name} -n '__fish_use_subcommand' -a {cmd} -d '{help}'",
+            name = name,
+            cmd = cmd,
+            help = help,
+        ));
+    }
+
+    // Free (positional) arguments aren't named flags, so there's no
+    // `-l`/`-s` to hang a completion off of; offer file completion for
+    // each position instead, gated on that position not already being
+    // taken by a subcommand name.
+    let first_free_position = if subcommands.is_empty() { 1 } else { 2 };
+
+    for (i, free_arg) in free.iter().enumerate() {
+        lines.push(format!(
+            "complete -c {name} -n '__fish_is_nth_token {position}' -F -d '{help}'",
+            name = name,
+            position = first_free_position + i,
+            help = free_arg.help,
+        ));
+    }
+
+    lines.join("\n") + "\n"
+}
+
+/// A [`Callable`] which prints a shell completion script for another
+/// `Options` type.
+///
+/// The script is rendered once by [`new`](CompletionsCommand::new) and
+/// printed on every [`call`](Callable::call). `CompletionsCommand` does not
+/// implement [`Options`], so it is not a
+/// [`Command`][crate::command::Command] and is not parsed from arguments as
+/// a subcommand; the application constructs it directly:
+///
+/// ```
+/// # use abscissa::options::{CompletionsCommand, Shell};
+/// # use abscissa::Options;
+/// # #[derive(Debug)]
+/// # struct MyOptions;
+/// # impl Options for MyOptions {
+/// #     fn parse_args<S: AsRef<str>>(_: &[S], _: abscissa::options::ParsingStyle)
+/// #         -> Result<Self, abscissa::options::Error> { Ok(MyOptions) }
+/// #     fn self_usage() -> &'static str { "" }
+/// # }
+/// let command = CompletionsCommand::new::<MyOptions>("my-app", Shell::Zsh);
+/// ```
+#[derive(Debug)]
+pub struct CompletionsCommand {
+    script: String,
+}
+
+impl CompletionsCommand {
+    /// Render a completion script for `T`'s option metadata under the
+    /// given binary `name` and target `shell`.
+    pub fn new<T: Options>(name: &str, shell: Shell) -> Self {
+        CompletionsCommand {
+            script: T::completions(name, shell),
+        }
+    }
+}
+
+impl Callable for CompletionsCommand {
+    fn call(&self) {
+        print!("{}", self.script);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPTS: &[OptSpec] = &[
+        OptSpec {
+            long: Some("verbose"),
+            short: Some('v'),
+            help: "be verbose",
+            arity: Arity::Flag,
+        },
+        OptSpec {
+            long: Some("output"),
+            short: Some('o'),
+            help: "output file",
+            arity: Arity::Single,
+        },
+    ];
+
+    const SUBCOMMANDS: &[(&str, &str)] = &[("start", "start the server"), ("stop", "stop the server")];
+
+    /// Every line of a `\`-continued zsh statement must end in a
+    /// continuation, except the last: a bare `"\n"` join between them
+    /// terminates the statement early and turns the remaining lines into
+    /// invalid top-level commands when the script is sourced.
+    fn assert_fully_continued(block: &str) {
+        let lines: Vec<&str> = block.lines().collect();
+        assert!(!lines.is_empty());
+
+        for line in &lines[..lines.len() - 1] {
+            assert!(
+                line.ends_with('\\'),
+                "line {:?} is missing its continuation backslash",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn zsh_script_continues_every_flag_and_command_line() {
+        let script = zsh("my-app", OPTS, &[], SUBCOMMANDS);
+
+        // Everything from "_arguments -C \" through the "'1: :->command'"
+        // positional spec is one continued statement: every line but the
+        // final one must end in a continuation backslash.
+        let arguments_stmt = script
+            .split("_arguments -C \\\n")
+            .nth(1)
+            .unwrap()
+            .split("\n\n  case")
+            .next()
+            .unwrap();
+        assert_fully_continued(arguments_stmt);
+
+        // Likewise for the "_values 'command' \" statement.
+        let values_stmt = script
+            .split("_values 'command' \\\n")
+            .nth(1)
+            .unwrap()
+            .split("\n      ;;")
+            .next()
+            .unwrap();
+        assert_fully_continued(values_stmt);
+
+        // Both flags and both subcommands must actually be present, not
+        // silently dropped by the broken join.
+        assert!(script.contains("--verbose"));
+        assert!(script.contains("--output"));
+        assert!(script.contains("'start:start the server'"));
+        assert!(script.contains("'stop:stop the server'"));
+    }
+
+    #[test]
+    fn bash_and_fish_scripts_include_every_flag_and_command() {
+        let bash_script = bash("my-app", OPTS, &[], SUBCOMMANDS);
+        assert!(bash_script.contains("--verbose"));
+        assert!(bash_script.contains("--output"));
+        assert!(bash_script.contains("start"));
+        assert!(bash_script.contains("stop"));
+
+        let fish_script = fish("my-app", OPTS, &[], SUBCOMMANDS);
+        assert!(fish_script.contains("-l verbose"));
+        assert!(fish_script.contains("-l output"));
+        assert!(fish_script.contains("-a start"));
+        assert!(fish_script.contains("-a stop"));
+    }
+
+    const FREE: &[FreeSpec] = &[FreeSpec {
+        name: "PATH",
+        help: "path to act on",
+    }];
+
+    #[test]
+    fn zsh_script_wires_in_free_arguments_and_value_arity() {
+        // A boolean flag gets no value-completion action...
+        let flag_only = zsh("my-app", OPTS, &[], &[]);
+        assert!(flag_only.contains("{-v,--verbose}'[be verbose]'"));
+        // ...but a `Single`-arity flag does.
+        assert!(flag_only.contains("{-o,--output}'[output file]:value:_files'"));
+
+        // With no subcommands, free arguments start at position 1.
+        let free_only = zsh("my-app", OPTS, FREE, &[]);
+        assert!(free_only.contains("'1:PATH:_files'"));
+        assert!(!free_only.contains("'1: :->command'"));
+
+        // With subcommands, position 1 is reserved for the subcommand
+        // name, so free arguments shift to 2.
+        let with_commands = zsh("my-app", OPTS, FREE, SUBCOMMANDS);
+        assert!(with_commands.contains("'1: :->command'"));
+        assert!(with_commands.contains("'2:PATH:_files'"));
+    }
+
+    #[test]
+    fn bash_script_offers_file_completion_for_value_flags_and_free_args() {
+        let script = bash("my-app", OPTS, FREE, &[]);
+        assert!(script.contains("value_opts=\"--output -o\""));
+        assert!(script.contains("compgen -f -- \"$cur\""));
+    }
+
+    #[test]
+    fn fish_script_marks_value_flags_and_free_args() {
+        let script = fish("my-app", OPTS, FREE, &[]);
+        assert!(script.contains("-l output -r -d 'output file'"));
+        assert!(!script.contains("-l verbose -r"));
+        assert!(script.contains("__fish_is_nth_token 1"));
+        assert!(script.contains("-d 'path to act on'"));
+    }
+}
@@ -0,0 +1,163 @@
+//! Secret values, parsed from configuration files or loaded from elsewhere
+//! (e.g. credentials loaded from the environment or network requests).
+//!
+//! [`Secret`] wraps a deserialized value and protects it in memory: its
+//! backing storage is zeroed when the `Secret` is dropped or overwritten,
+//! its `Debug` and `Display` impls both print a fixed redacted placeholder
+//! rather than the wrapped value, and — for byte-like contents — equality
+//! is checked in constant time so comparing a `Secret` against a candidate
+//! doesn't leak timing information about where the two first differ.
+
+use std::fmt;
+use std::mem;
+
+use serde::de::{Deserialize, Deserializer};
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+/// Placeholder printed in place of a [`Secret`]'s contents.
+const REDACTED: &str = "[REDACTED]";
+
+/// A secret value which zeroizes its backing memory on drop, and redacts
+/// itself in `Debug`/`Display` output.
+pub struct Secret<T: Zeroize>(T);
+
+impl<T: Zeroize> Secret<T> {
+    /// Wrap `value` as a secret.
+    pub fn new(value: T) -> Self {
+        Secret(value)
+    }
+
+    /// Borrow the secret's value.
+    ///
+    /// This is a named method rather than a `Deref` impl so that every
+    /// place a secret's contents are actually used is an explicit,
+    /// greppable call site.
+    pub fn expose_secret(&self) -> &T {
+        &self.0
+    }
+
+    /// Replace this secret's value, zeroizing the value being replaced.
+    pub fn set(&mut self, mut value: T) {
+        mem::swap(&mut self.0, &mut value);
+        value.zeroize();
+    }
+}
+
+impl<T: Zeroize> Drop for Secret<T> {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<T: Zeroize> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Secret").field(&REDACTED).finish()
+    }
+}
+
+impl<T: Zeroize> fmt::Display for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Secret<T>
+where
+    T: Zeroize + Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(Secret::new)
+    }
+}
+
+impl<T: Zeroize + AsRef<[u8]>> PartialEq for Secret<T> {
+    /// Compare two secrets' contents in constant time, so a failed
+    /// comparison (e.g. checking a candidate password or token) doesn't
+    /// leak how many leading bytes matched.
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_ref().ct_eq(other.0.as_ref()).into()
+    }
+}
+
+impl<T: Zeroize + AsRef<[u8]>> Eq for Secret<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    /// A `Zeroize` value that flags when `zeroize()` is called on it, so
+    /// tests can observe that `Secret`'s `Drop`/`set` actually delegate to
+    /// the wrapped value's `Zeroize` impl rather than, say, just dropping
+    /// it normally.
+    struct Tracked {
+        data: Vec<u8>,
+        zeroized: Arc<AtomicBool>,
+    }
+
+    impl Zeroize for Tracked {
+        fn zeroize(&mut self) {
+            self.data.zeroize();
+            self.zeroized.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn drop_zeroizes_the_wrapped_value() {
+        let zeroized = Arc::new(AtomicBool::new(false));
+
+        {
+            let _secret = Secret::new(Tracked {
+                data: vec![1, 2, 3],
+                zeroized: zeroized.clone(),
+            });
+            assert!(!zeroized.load(Ordering::SeqCst));
+        }
+
+        assert!(zeroized.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn set_zeroizes_the_replaced_value() {
+        let zeroized = Arc::new(AtomicBool::new(false));
+        let mut secret = Secret::new(Tracked {
+            data: vec![1, 2, 3],
+            zeroized: zeroized.clone(),
+        });
+
+        secret.set(Tracked {
+            data: vec![4, 5, 6],
+            zeroized: Arc::new(AtomicBool::new(false)),
+        });
+
+        assert!(zeroized.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn debug_and_display_never_contain_the_wrapped_value() {
+        let secret = Secret::new("hunter2".to_string());
+
+        let debug = format!("{:?}", secret);
+        let display = format!("{}", secret);
+
+        assert!(!debug.contains("hunter2"));
+        assert!(!display.contains("hunter2"));
+        assert!(debug.contains(REDACTED));
+        assert_eq!(display, REDACTED);
+    }
+
+    #[test]
+    fn equality_compares_contents_not_identity() {
+        let a = Secret::new(b"same-token".to_vec());
+        let b = Secret::new(b"same-token".to_vec());
+        let c = Secret::new(b"different-token".to_vec());
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+}
@@ -0,0 +1,38 @@
+//! Miscellaneous utilities.
+
+use std::fmt;
+
+/// An application's name and version, e.g. for status output or (with the
+/// `logging` feature's JSON mode) as static context attached to every
+/// emitted log record.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Version {
+    name: String,
+    version: String,
+}
+
+impl Version {
+    /// Create a new `Version` from an application name and version string.
+    pub fn new(name: impl Into<String>, version: impl Into<String>) -> Self {
+        Version {
+            name: name.into(),
+            version: version.into(),
+        }
+    }
+
+    /// The application's name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The application's version.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.name, self.version)
+    }
+}